@@ -1,228 +1,552 @@
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 use std::vec::Vec;
 
-fn count_suffix_zero_bits(ip: u64) -> u8 {
+/// Builds a prefix mask of the given bit `width` (32 for IPv4, 128 for
+/// IPv6) in a `u128`; callers narrow the result to their own integer type.
+fn make_mask128(prefix: u8, width: u8) -> u128 {
+    if prefix == 0 {
+        return 0;
+    }
+    if prefix >= width {
+        return if width >= 128 { u128::MAX } else { (1u128 << width) - 1 };
+    }
+    let full: u128 = if width >= 128 { u128::MAX } else { (1u128 << width) - 1 };
+    let n = width - prefix;
+    (full >> n) << n
+}
+
+fn count_suffix_zero_bits(ip: u128, width: u8) -> u8 {
     let mut i = 0;
     let mut ip = ip;
-    while (i <= 32) && ((ip & 0x1) == 0x0) {
+    while (i <= width) && ((ip & 0x1) == 0x0) {
         i += 1;
         ip >>= 1
     }
     return i;
 }
 
-fn make_mask(prefix: u8) -> u32 {
-    if prefix == 0 {
-        return 0;
-    }
-    let mask: u32 = 0xffffffff;
-    if prefix < 32 {
-        let n = 32 - prefix;
-        return (mask >> n) << n;
+/// Widest CIDR block (as a `u128` "all ones up to `s` low bits" mask) whose
+/// host count is `2^s`, clamped so the shift never exceeds `width`.
+fn block_mask(s: u8, width: u8) -> u128 {
+    if s >= width {
+        if width >= 128 {
+            u128::MAX
+        } else {
+            (1u128 << width) - 1
+        }
+    } else if s == 0 {
+        0
+    } else {
+        (1u128 << s) - 1
     }
-    return mask;
 }
 
-fn mask_ipaddr(ip: Ipv4Addr, prefix: u8) -> Ipv4Addr {
-    return ip & Ipv4Addr::from(make_mask(prefix));
-}
+/// Generates an `Ipv4`-/`Ipv6`-flavoured range+subnet pair backed by `$uint`
+/// (`u32` or `u128`). Keeps `make_mask`/`get_prefix`/`to_subnets` etc.
+/// working at the right bit `$width` without duplicating the logic by hand
+/// for each address family.
+macro_rules! define_ip_family {
+    ($range:ident, $subnet:ident, $addrrange:ident, $addr:ty, $uint:ty, $count:ty, $width:expr) => {
+        #[derive(Debug, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
+        pub struct $range {
+            start: $addr,
+            end: $addr,
+        }
 
-#[derive(Debug, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
-pub struct Ipv4Range {
-    start: Ipv4Addr,
-    end: Ipv4Addr,
-}
+        impl $range {
+            pub fn start(self: &Self) -> $addr {
+                self.start
+            }
 
-impl Ipv4Range {
-    pub fn start(self: &Self) -> Ipv4Addr {
-        self.start
-    }
+            pub fn end(self: &Self) -> $addr {
+                self.end
+            }
 
-    pub fn end(self: &Self) -> Ipv4Addr {
-        self.end
-    }
+            pub fn update_end(self: &mut Self, end: $addr) -> bool {
+                if end < self.start {
+                    false
+                } else {
+                    self.end = end;
+                    true
+                }
+            }
 
-    /*
-     *pub fn update_start(self: &mut Self, start: Ipv4Addr) -> bool {
-     *    if start > self.end {
-     *        false
-     *    } else {
-     *        self.start = start;
-     *        true
-     *    }
-     *}
-     */
-    pub fn update_end(self: &mut Self, end: Ipv4Addr) -> bool {
-        if end < self.start {
-            false
-        } else {
-            self.end = end;
-            true
+            /// Whether every address in `other` is also in `self`.
+            pub fn contains(self: &Self, other: &Self) -> bool {
+                self.start <= other.start && self.end >= other.end
+            }
+
+            /// Number of addresses covered by this range. Saturates at
+            /// `$count::MAX` rather than overflow on a full address space
+            /// (e.g. `::` - `ffff:...:ffff`, which doesn't fit any fixed
+            /// integer width).
+            pub fn host_count(self: &Self) -> $count {
+                let start: $uint = self.start.into();
+                let end: $uint = self.end.into();
+                (end as $count).saturating_sub(start as $count).saturating_add(1)
+            }
+
+            pub fn iter(self: &Self) -> $addrrange {
+                (*self).into_iter()
+            }
+
+            /// The minimal set of ranges covering `self` minus `holes`:
+            /// clamps each hole to `self`, then walks `self` from its
+            /// start, emitting a range for each gap before the next hole.
+            pub fn exclude(self: &Self, holes: &[$range]) -> Vec<$range> {
+                let mut holes: Vec<$range> = holes
+                    .iter()
+                    .filter_map(|h| {
+                        let start = std::cmp::max(h.start, self.start);
+                        let end = std::cmp::min(h.end, self.end);
+                        if start <= end {
+                            Some($range { start, end })
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+                holes.sort();
+
+                let mut result: Vec<$range> = Vec::new();
+                let mut cursor: $uint = self.start.into();
+                for hole in &holes {
+                    let hole_start: $uint = hole.start.into();
+                    let hole_end: $uint = hole.end.into();
+                    if hole_start > cursor {
+                        result.push($range {
+                            start: <$addr>::from(cursor),
+                            end: <$addr>::from(hole_start - 1),
+                        });
+                    }
+                    cursor = match hole_end.checked_add(1) {
+                        Some(next) => std::cmp::max(cursor, next),
+                        // The hole reaches the top of the address space;
+                        // nothing can remain after it.
+                        None => return result,
+                    };
+                }
+                let end: $uint = self.end.into();
+                if cursor <= end {
+                    result.push($range {
+                        start: <$addr>::from(cursor),
+                        end: <$addr>::from(end),
+                    });
+                }
+                result
+            }
+
+            fn get_prefix(self: &Self) -> u8 {
+                for i in 0..$width {
+                    let start: $uint = self.start.into();
+                    let end: $uint = self.end.into();
+                    if (start >> i) == (end >> i) {
+                        return $width - i;
+                    }
+                }
+                return 0;
+            }
+
+            pub fn parse_range(a: &str) -> Result<$range, &'static str> {
+                if let Some(n) = a.find('/') {
+                    let Ok(prefix) = u8::from_str(&a[n + 1..]) else {
+                        return Err("Invalid IP subnet prefix");
+                    };
+                    let Ok(addr) = <$addr>::from_str(&a[..n]) else {
+                        return Err("Invalid IP address");
+                    };
+                    return $range::try_from((addr, prefix));
+                } else if let Some(n) = a.find('-') {
+                    let Ok(iprange_start) = <$addr>::from_str(a[..n].trim()) else {
+                        return Err("Invalid IP address");
+                    };
+                    let Ok(iprange_end) = <$addr>::from_str(a[n + 1..].trim()) else {
+                        return Err("Invalid IP address");
+                    };
+                    return $range::try_from((iprange_start, iprange_end));
+                }
+                Err("Invalid IP range/subnet")
+            }
+
+            /// Expects a sorted `ranges`, in non-decreasing order. Merges
+            /// overlapping *and* adjacent ranges in place.
+            pub fn merge_ranges(ranges: &mut Vec<$range>) {
+                let n = ranges.len();
+                if n < 2 {
+                    return;
+                }
+
+                let mut j = 0;
+                for i in 1..n {
+                    let start: $uint = ranges[i].start().into();
+                    let end: $uint = ranges[j].end().into();
+                    // start <= end + 1, without risking overflow when end
+                    // is the family's highest address.
+                    let overlaps_or_adjacent = match start.checked_sub(1) {
+                        Some(start_minus_one) => start_minus_one <= end,
+                        None => true,
+                    };
+                    if !overlaps_or_adjacent {
+                        j += 1;
+                        ranges[j] = ranges[i];
+                    } else {
+                        let other_end = ranges[i].end();
+                        if other_end > ranges[j].end() {
+                            ranges[j].update_end(other_end);
+                        }
+                    }
+                }
+                j += 1;
+                ranges.drain(j..);
+            }
+
+            pub fn to_subnets(self: &Self) -> Vec<$subnet> {
+                let mut vec: Vec<$subnet> = Vec::new();
+                let start: $uint = self.start().into();
+                let end: $uint = self.end().into();
+                let mut start: u128 = start as u128;
+                let end: u128 = end as u128;
+                loop {
+                    let mut s: u8 = count_suffix_zero_bits(start, $width);
+                    if s > $width {
+                        s = $width;
+                    }
+                    let mut diff: u128 = block_mask(s, $width);
+                    while diff > 0 && start.checked_add(diff).map_or(true, |v| v > end) {
+                        s -= 1;
+                        diff = block_mask(s, $width);
+                    }
+                    vec.push($subnet::try_from((start as $uint, $width - s)).unwrap());
+                    // start + diff cannot overflow here: the loop above
+                    // only stops once it fits under `end`.
+                    let subnet_end = start + diff;
+                    if subnet_end >= end {
+                        break;
+                    }
+                    start = subnet_end + 1;
+                }
+                return vec;
+            }
         }
-    }
 
-    fn get_prefix(self: &Self) -> u8 {
-        for i in 0..32 {
-            let start: u32 = self.start.into();
-            let end: u32 = self.end.into();
-            if (start >> i) == (end >> i) {
-                return 32 - i;
+        impl FromStr for $range {
+            type Err = &'static str;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $range::parse_range(s)
             }
         }
-        return 0;
-    }
 
-    pub fn parse_range(a: &str) -> Result<Ipv4Range, &'static str> {
-        if let Some(n) = a.find('/') {
-            let Ok(prefix) = u8::from_str(&a[n + 1..]) else {
-                return Err("Invalid IP subnet prefix");
-            };
-            let Ok(addr) = Ipv4Addr::from_str(&a[..n]) else {
-                return Err("Invalid IP address");
-            };
-            return Ipv4Range::try_from((addr, prefix));
-        } else if let Some(n) = a.find('-') {
-            let Ok(iprange_start) = Ipv4Addr::from_str(a[..n].trim()) else {
-                return Err("Invalid IP address");
-            };
-            let Ok(iprange_end) = Ipv4Addr::from_str(a[n + 1..].trim()) else {
-                return Err("Invalid IP address");
-            };
-            return Ipv4Range::try_from((iprange_start, iprange_end));
-        }
-        Err("Invalid IP range/subnet")
-    }
+        impl std::convert::TryFrom<($addr, $addr)> for $range {
+            type Error = &'static str;
+            fn try_from(t: ($addr, $addr)) -> Result<Self, Self::Error> {
+                if t.0 > t.1 {
+                    Err("Invalid Range")
+                } else {
+                    Ok($range {
+                        start: t.0,
+                        end: t.1,
+                    })
+                }
+            }
+        }
 
-    pub fn to_subnets(self: &Self) -> Vec<Ipv4Subnet> {
-        let mut vec: Vec<Ipv4Subnet> = Vec::new();
-        let start: u32 = self.start().into();
-        let end: u32 = self.end().into();
-        let mut start: u64 = start as u64;
-        let end: u64 = end as u64;
-        while start <= end {
-            let mut s: u8 = count_suffix_zero_bits(start);
-            let mut diff: u64 = (1u64 << s) - 1;
-            while (start + diff) > end {
-                diff >>= 1;
-                s -= 1;
-            }
-            vec.push(Ipv4Subnet::try_from((start as u32, 32u8 - s)).unwrap());
-            start += diff + 1;
-        }
-        return vec;
-    }
-}
+        impl std::convert::TryFrom<($addr, u8)> for $range {
+            type Error = &'static str;
+            fn try_from(t: ($addr, u8)) -> Result<Self, Self::Error> {
+                Ok($range::from(&$subnet::try_from(t)?))
+            }
+        }
 
-impl FromStr for Ipv4Range {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ipv4Range::parse_range(s)
-    }
-}
+        impl std::convert::From<&$subnet> for $range {
+            fn from(subnet: &$subnet) -> Self {
+                Self {
+                    start: subnet.start_addr(),
+                    end: subnet.end_addr(),
+                }
+            }
+        }
 
-impl std::convert::TryFrom<(Ipv4Addr, Ipv4Addr)> for Ipv4Range {
-    type Error = &'static str;
-    fn try_from(t: (Ipv4Addr, Ipv4Addr)) -> Result<Self, Self::Error> {
-        if t.0 > t.1 {
-            Err("Invalid Range")
-        } else {
-            Ok(Ipv4Range {
-                start: t.0,
-                end: t.1,
-            })
+        impl std::fmt::Display for $range {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{} - {}", self.start, self.end)
+            }
         }
-    }
-}
 
-impl std::convert::TryFrom<(Ipv4Addr, u8)> for Ipv4Range {
-    type Error = &'static str;
-    fn try_from(t: (Ipv4Addr, u8)) -> Result<Self, Self::Error> {
-        Ok(Ipv4Range::from(&Ipv4Subnet::try_from(t)?))
-    }
-}
+        #[derive(Debug, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
+        pub struct $subnet {
+            addr: $addr,
+            prefix: u8,
+        }
+
+        impl $subnet {
+            pub fn prefix(self: &Self) -> u8 {
+                self.prefix
+            }
 
-impl std::convert::From<&Ipv4Subnet> for Ipv4Range {
-    fn from(ipsubnet: &Ipv4Subnet) -> Self {
-        Self {
-            start: ipsubnet.start_addr(),
-            end: ipsubnet.end_addr(),
+            fn make_mask(prefix: u8) -> $uint {
+                make_mask128(prefix, $width) as $uint
+            }
+
+            fn mask_addr(addr: $addr, prefix: u8) -> $addr {
+                let bits: $uint = addr.into();
+                <$addr>::from(bits & Self::make_mask(prefix))
+            }
+
+            fn start_addr(self: &Self) -> $addr {
+                Self::mask_addr(self.addr, self.prefix)
+            }
+
+            fn end_addr(self: &Self) -> $addr {
+                let start: $uint = Self::mask_addr(self.addr, self.prefix).into();
+                let host_mask = !Self::make_mask(self.prefix);
+                <$addr>::from(start | host_mask)
+            }
+
+            /// The lowest address in the subnet.
+            pub fn network(self: &Self) -> $addr {
+                self.start_addr()
+            }
+
+            /// The highest address in the subnet.
+            pub fn broadcast(self: &Self) -> $addr {
+                self.end_addr()
+            }
+
+            /// Whether `other` lies fully inside `self` (as in wgconfd's
+            /// `Net::contains`): a shorter-or-equal prefix whose masked
+            /// address matches `other`'s under the same mask. Prefix 0
+            /// always contains, since shifting by the full bit width would
+            /// otherwise overflow.
+            pub fn contains(self: &Self, other: &Self) -> bool {
+                if self.prefix == 0 {
+                    return true;
+                }
+                if self.prefix > other.prefix {
+                    return false;
+                }
+                let shift = $width - self.prefix;
+                let a: $uint = self.addr.into();
+                let b: $uint = other.addr.into();
+                (a >> shift) == (b >> shift)
+            }
         }
-    }
-}
 
-impl std::fmt::Display for Ipv4Range {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{} - {}", self.start, self.end)
-    }
+        impl FromStr for $subnet {
+            type Err = &'static str;
+            fn from_str(s: &str) -> Result<$subnet, Self::Err> {
+                let Some(n) = s.find('/') else {
+                    return Err("Invalid subnet string");
+                };
+                let Ok(prefix) = u8::from_str(&s[n + 1..]) else {
+                    return Err("Invalid subnet prefix");
+                };
+                let Ok(addr) = <$addr>::from_str(&s[..n]) else {
+                    return Err("Invalid IP address");
+                };
+                $subnet::try_from((addr, prefix))
+            }
+        }
+
+        impl std::convert::TryFrom<($addr, u8)> for $subnet {
+            type Error = &'static str;
+            fn try_from(t: ($addr, u8)) -> Result<Self, <Self as TryFrom<($addr, u8)>>::Error> {
+                if t.1 > $width {
+                    Err("Invalid IP subnet prefix")
+                } else {
+                    Ok(Self {
+                        addr: t.0,
+                        prefix: t.1,
+                    })
+                }
+            }
+        }
+
+        impl std::convert::TryFrom<($uint, u8)> for $subnet {
+            type Error = &'static str;
+            fn try_from(t: ($uint, u8)) -> Result<Self, <Self as TryFrom<($uint, u8)>>::Error> {
+                Self::try_from((<$addr>::from(t.0), t.1))
+            }
+        }
+
+        impl std::convert::From<&$range> for $subnet {
+            fn from(iprange: &$range) -> Self {
+                Self {
+                    addr: iprange.start,
+                    prefix: iprange.get_prefix(),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $subnet {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{}/{}", self.addr, self.prefix)
+            }
+        }
+
+        /// Iterates every address in an `$range`, in order.
+        #[derive(Debug, Clone)]
+        pub struct $addrrange {
+            next: $uint,
+            end: $uint,
+            exhausted: bool,
+        }
+
+        impl Iterator for $addrrange {
+            type Item = $addr;
+            fn next(&mut self) -> Option<$addr> {
+                if self.exhausted {
+                    return None;
+                }
+                let item = self.next;
+                if item == self.end {
+                    self.exhausted = true;
+                } else {
+                    self.next = item.saturating_add(1);
+                }
+                Some(<$addr>::from(item))
+            }
+        }
+
+        impl DoubleEndedIterator for $addrrange {
+            fn next_back(&mut self) -> Option<$addr> {
+                if self.exhausted {
+                    return None;
+                }
+                let item = self.end;
+                if item == self.next {
+                    self.exhausted = true;
+                } else {
+                    self.end -= 1;
+                }
+                Some(<$addr>::from(item))
+            }
+        }
+
+        impl std::iter::FusedIterator for $addrrange {}
+
+        impl std::iter::IntoIterator for $range {
+            type Item = $addr;
+            type IntoIter = $addrrange;
+            fn into_iter(self) -> $addrrange {
+                $addrrange {
+                    next: self.start.into(),
+                    end: self.end.into(),
+                    exhausted: false,
+                }
+            }
+        }
+    };
 }
 
-#[derive(Debug, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
-pub struct Ipv4Subnet {
-    addr: Ipv4Addr,
-    prefix: u8,
+define_ip_family!(Ipv4Range, Ipv4Subnet, Ipv4AddrRange, Ipv4Addr, u32, u64, 32);
+define_ip_family!(Ipv6Range, Ipv6Subnet, Ipv6AddrRange, Ipv6Addr, u128, u128, 128);
+
+/// An IP range of either family, as produced by auto-detecting the literal
+/// passed on the command line (an IPv6 literal always contains a `:`).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum IpRange {
+    V4(Ipv4Range),
+    V6(Ipv6Range),
 }
 
-impl Ipv4Subnet {
-    fn start_addr(self: &Self) -> Ipv4Addr {
-        mask_ipaddr(self.addr, self.prefix)
+impl IpRange {
+    pub fn parse_range(a: &str) -> Result<IpRange, &'static str> {
+        if a.contains(':') {
+            Ipv6Range::parse_range(a).map(IpRange::V6)
+        } else {
+            Ipv4Range::parse_range(a).map(IpRange::V4)
+        }
+    }
+
+    /// Like `parse_range`, but also accepts a bare address (treated as a
+    /// single-address range), for commands that take either a range/subnet
+    /// or a plain address (e.g. `-c`'s container/candidates, `-n`'s and
+    /// `--enumerate`'s arguments). Accepts the same dotted-quad/colon-hex,
+    /// decimal and hexadecimal forms as plain address conversion mode.
+    pub fn parse(a: &str) -> Result<IpRange, &'static str> {
+        if let Ok(r) = IpRange::parse_range(a) {
+            return Ok(r);
+        }
+        if let Ok(addr) = Ipv4Addr::from_str(a) {
+            return Ok(IpRange::V4(Ipv4Range::try_from((addr, addr)).unwrap()));
+        }
+        if let Ok(ip) = a.parse::<u32>() {
+            let addr = Ipv4Addr::from(ip);
+            return Ok(IpRange::V4(Ipv4Range::try_from((addr, addr)).unwrap()));
+        }
+        if let Ok(addr) = Ipv6Addr::from_str(a) {
+            return Ok(IpRange::V6(Ipv6Range::try_from((addr, addr)).unwrap()));
+        }
+        if let Ok(ip) = a.parse::<u128>() {
+            let addr = Ipv6Addr::from(ip);
+            return Ok(IpRange::V6(Ipv6Range::try_from((addr, addr)).unwrap()));
+        }
+        let hex = a.strip_prefix("0x").unwrap_or(a);
+        if let Ok(ip) = u32::from_str_radix(hex, 16) {
+            let addr = Ipv4Addr::from(ip);
+            return Ok(IpRange::V4(Ipv4Range::try_from((addr, addr)).unwrap()));
+        }
+        if let Ok(ip) = u128::from_str_radix(hex, 16) {
+            let addr = Ipv6Addr::from(ip);
+            return Ok(IpRange::V6(Ipv6Range::try_from((addr, addr)).unwrap()));
+        }
+        if a.contains(':') {
+            Err("Invalid IPv6 address")
+        } else {
+            Err("Invalid IPv4 address")
+        }
     }
-    fn end_addr(self: &Self) -> Ipv4Addr {
-        let start = mask_ipaddr(self.addr, self.prefix);
-        &start | Ipv4Addr::from(!make_mask(self.prefix))
+
+    /// Whether every address in `other` is also in `self`. Ranges from
+    /// different address families never contain one another.
+    pub fn contains(self: &Self, other: &IpRange) -> bool {
+        match (self, other) {
+            (IpRange::V4(a), IpRange::V4(b)) => a.contains(b),
+            (IpRange::V6(a), IpRange::V6(b)) => a.contains(b),
+            _ => false,
+        }
     }
 }
 
-impl FromStr for Ipv4Subnet {
+impl FromStr for IpRange {
     type Err = &'static str;
-    fn from_str(s: &str) -> Result<Ipv4Subnet, Self::Err> {
-        let Some(n) = s.find('/') else {
-            return Err("Invalid subnet string");
-        };
-        // A subnet (eg. 192.168.18.0/24)
-        let Ok(prefix) = u8::from_str(&s[n + 1..]) else {
-            return Err("Invalid subnet prefix");
-        };
-        let Ok(addr) = Ipv4Addr::from_str(&s[..n]) else {
-            return Err("Invalid IP address");
-        };
-        Ipv4Subnet::try_from((addr, prefix))
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IpRange::parse_range(s)
     }
 }
 
-impl std::convert::TryFrom<(Ipv4Addr, u8)> for Ipv4Subnet {
-    type Error = &'static str;
-    fn try_from(t: (Ipv4Addr, u8)) -> Result<Self, <Self as TryFrom<(Ipv4Addr, u8)>>::Error> {
-        if t.1 > 32 {
-            Err("Invalid IP subnet prefix")
-        } else {
-            Ok(Self {
-                addr: t.0,
-                prefix: t.1,
-            })
+impl std::fmt::Display for IpRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            IpRange::V4(r) => write!(f, "{}", r),
+            IpRange::V6(r) => write!(f, "{}", r),
         }
     }
 }
 
-impl std::convert::TryFrom<(u32, u8)> for Ipv4Subnet {
-    type Error = &'static str;
-    fn try_from(t: (u32, u8)) -> Result<Self, <Self as TryFrom<(u32, u8)>>::Error> {
-        Self::try_from((Ipv4Addr::from(t.0), t.1))
-    }
+/// An IP subnet of either family; the `IpRange` counterpart.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum IpSubnet {
+    V4(Ipv4Subnet),
+    V6(Ipv6Subnet),
 }
 
-impl std::convert::From<&Ipv4Range> for Ipv4Subnet {
-    fn from(iprange: &Ipv4Range) -> Self {
-        Self {
-            addr: iprange.start,
-            prefix: iprange.get_prefix(),
+impl std::convert::From<&IpRange> for IpSubnet {
+    fn from(iprange: &IpRange) -> Self {
+        match iprange {
+            IpRange::V4(r) => IpSubnet::V4(Ipv4Subnet::from(r)),
+            IpRange::V6(r) => IpSubnet::V6(Ipv6Subnet::from(r)),
         }
     }
 }
 
-impl std::fmt::Display for Ipv4Subnet {
+impl std::fmt::Display for IpSubnet {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}/{}", self.addr, self.prefix)
+        match self {
+            IpSubnet::V4(s) => write!(f, "{}", s),
+            IpSubnet::V6(s) => write!(f, "{}", s),
+        }
     }
 }
 
@@ -246,3 +570,77 @@ fn range_to_subnet_conversion() {
     let s: Ipv4Subnet = Ipv4Subnet::from_str("0.0.0.0/0").unwrap();
     assert_eq!(r.to_subnets(), vec![s]);
 }
+
+#[test]
+fn ipv6_range_to_subnet_conversion() {
+    let r: Ipv6Range = Ipv6Range::from_str("2001:db8:: - 2001:db8::1").unwrap();
+    let s: Ipv6Subnet = Ipv6Subnet::from_str("2001:db8::/127").unwrap();
+    assert_eq!(r.to_subnets(), vec![s]);
+
+    let r: Ipv6Range = Ipv6Range::from_str(":: - ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff").unwrap();
+    let s: Ipv6Subnet = Ipv6Subnet::from_str("::/0").unwrap();
+    assert_eq!(r.to_subnets(), vec![s]);
+}
+
+#[test]
+fn subnet_and_range_contains() {
+    let container: Ipv4Subnet = "10.0.0.0/16".parse().unwrap();
+    assert!(container.contains(&"10.0.5.0/24".parse().unwrap()));
+    assert!(!container.contains(&"10.1.0.0/24".parse().unwrap()));
+
+    let container: Ipv4Range = Ipv4Range::from(&container);
+    assert!(container.contains(&Ipv4Range::try_from((Ipv4Addr::new(10, 0, 5, 7), Ipv4Addr::new(10, 0, 5, 7))).unwrap()));
+
+    assert!(IpRange::parse("10.0.0.0/16")
+        .unwrap()
+        .contains(&IpRange::parse("10.0.5.7").unwrap()));
+    assert!(!IpRange::parse("10.0.0.0/16")
+        .unwrap()
+        .contains(&IpRange::parse("2001:db8::1").unwrap()));
+}
+
+#[test]
+fn host_count_and_iteration() {
+    let r: Ipv4Range = "10.0.0.0/24".parse().unwrap();
+    assert_eq!(r.host_count(), 256);
+    assert_eq!(r.iter().count(), 256);
+    assert_eq!(r.iter().next(), Some(Ipv4Addr::new(10, 0, 0, 0)));
+    assert_eq!(r.iter().next_back(), Some(Ipv4Addr::new(10, 0, 0, 255)));
+
+    let full: Ipv4Range = "0.0.0.0/0".parse().unwrap();
+    assert_eq!(full.host_count(), 1u64 << 32);
+}
+
+#[test]
+fn exclude_carves_holes_out_of_a_base_range() {
+    let base: Ipv4Range = "10.0.0.0-10.0.0.255".parse().unwrap();
+    let holes = vec![
+        "10.0.0.64/26".parse::<Ipv4Range>().unwrap(),
+        "10.0.0.250-10.0.0.255".parse::<Ipv4Range>().unwrap(),
+    ];
+    let remaining = base.exclude(&holes);
+    assert_eq!(
+        remaining,
+        vec![
+            "10.0.0.0-10.0.0.63".parse::<Ipv4Range>().unwrap(),
+            "10.0.0.128-10.0.0.249".parse::<Ipv4Range>().unwrap(),
+        ]
+    );
+
+    // A hole that reaches the top of the address space leaves nothing after it.
+    let base: Ipv4Range = "255.255.255.0/24".parse().unwrap();
+    let holes = vec!["255.255.255.128-255.255.255.255".parse::<Ipv4Range>().unwrap()];
+    assert_eq!(
+        base.exclude(&holes),
+        vec!["255.255.255.0-255.255.255.127".parse::<Ipv4Range>().unwrap()]
+    );
+}
+
+#[test]
+fn ip_range_auto_detects_family() {
+    assert!(matches!(IpRange::parse_range("10.0.0.0/8"), Ok(IpRange::V4(_))));
+    assert!(matches!(
+        IpRange::parse_range("2001:db8::/32"),
+        Ok(IpRange::V6(_))
+    ));
+}
@@ -1,8 +1,11 @@
+mod iprange;
+
+use iprange::{IpRange, IpSubnet, Ipv4Range, Ipv4Subnet, Ipv6Range, Ipv6Subnet};
 use std::env;
 use std::io::stdin;
 use std::io::BufRead;
 use std::iter::Iterator;
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 use std::vec::Vec;
 
@@ -23,6 +26,8 @@ const HELP_STR: &str = r#"ripcal [-i | -x | -q ] [-r] [<ip-address>...]
         If no ip-address arguments are given, then it'll
         read from stdin and output to stdout (filter mode)
 
+        IPv4 and IPv6 addresses are both accepted.
+
 ripcal <ip-addr/subnet> | \"<ip-start - ip-end>\"
         ip-addr/subnet will be converted to the corresponding
         ip-range (\"start - end\"). \"start - end\" (ip-range)
@@ -34,6 +39,29 @@ ripcal -m (<ip-addr/subnet> | <ip-range>)...
         set of ranges and subnets that exactly covers the
         specified subnets/ranges on the command line.
 
+ripcal -c <container> <candidate>...
+        Checks whether each <candidate> (an address, subnet or
+        range) lies fully inside <container> (an address, subnet
+        or range), and prints the verdict for each one.
+
+ripcal -n <ip-addr/subnet | ip-range>...
+        Prints the number of addresses covered by each argument,
+        e.g. "ripcal -n 10.0.0.0/24" prints "256".
+
+ripcal --enumerate <ip-addr/subnet | ip-range>...
+        Prints every address covered by each argument, one per
+        line.
+
+ripcal --exclude <base> <hole>...
+        Carves each <hole> (an address, subnet or range) out of
+        <base> (an address, subnet or range), and prints the
+        minimal set of ranges and subnets that covers what's left.
+
+ripcal --json [...]
+        Emits structured JSON instead of plain text for address
+        conversions, subnet/range lookups, --merge-ranges and
+        --exclude, so ripcal can be used as a pipeline component.
+
 ripcal -h or ripcal --help
         displays this help
 
@@ -61,6 +89,9 @@ struct Config {
     reverse_bytes: bool,
     filter_mode: bool,
     output_type: Option<OutputType>,
+    count_mode: bool,
+    enumerate_mode: bool,
+    json: bool,
 }
 
 impl Config {
@@ -69,156 +100,13 @@ impl Config {
             reverse_bytes: false,
             filter_mode: false,
             output_type: None,
+            count_mode: false,
+            enumerate_mode: false,
+            json: false,
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
-struct Ipv4Range {
-    start: Ipv4Addr,
-    end: Ipv4Addr,
-}
-
-impl std::convert::TryFrom<(Ipv4Addr, Ipv4Addr)> for Ipv4Range {
-    type Error = &'static str;
-    fn try_from(t: (Ipv4Addr, Ipv4Addr)) -> Result<Self, Self::Error> {
-        if t.0 > t.1 {
-            Err("Invalid Range")
-        } else {
-            Ok(Ipv4Range {
-                start: t.0,
-                end: t.1,
-            })
-        }
-    }
-}
-
-impl std::convert::TryFrom<(Ipv4Addr, u8)> for Ipv4Range {
-    type Error = &'static str;
-    fn try_from(t: (Ipv4Addr, u8)) -> Result<Self, Self::Error> {
-        Ok(Ipv4Range::from(&Ipv4Subnet::try_from(t)?))
-    }
-}
-
-impl std::convert::From<&Ipv4Subnet> for Ipv4Range {
-    fn from(ipsubnet: &Ipv4Subnet) -> Self {
-        Self {
-            start: ipsubnet.start_addr(),
-            end: ipsubnet.end_addr(),
-        }
-    }
-}
-
-impl std::fmt::Display for Ipv4Range {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{} - {}", self.start, self.end)
-    }
-}
-
-impl Ipv4Range {
-    fn get_prefix(self: &Self) -> u8 {
-        for i in 0..32 {
-            let start: u32 = self.start.into();
-            let end: u32 = self.end.into();
-            if (start >> i) == (end >> i) {
-                return 32 - i;
-            }
-        }
-        return 0;
-    }
-
-    fn parse_range(a: &str) -> Result<Ipv4Range, &'static str> {
-        if let Some(n) = a.find('/') {
-            if let Ok(prefix) = u8::from_str(&a[n + 1..]) {
-                if let Ok(addr) = Ipv4Addr::from_str(&a[..n]) {
-                    return Ipv4Range::try_from((addr, prefix));
-                }
-            }
-            return Err("Invalid IP subnet");
-        } else if let Some(n) = a.find('-') {
-            if let Ok(iprange_start) = Ipv4Addr::from_str(a[..n].trim()) {
-                if let Ok(iprange_end) = Ipv4Addr::from_str(a[n + 1..].trim()) {
-                    return Ipv4Range::try_from((iprange_start, iprange_end));
-                }
-            }
-            return Err("Invalid IP range");
-        }
-        Err("Invalid range/subnet")
-    }
-}
-
-#[derive(Debug, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
-struct Ipv4Subnet {
-    addr: Ipv4Addr,
-    prefix: u8,
-}
-
-impl Ipv4Subnet {
-    fn start_addr(self: &Self) -> Ipv4Addr {
-        mask_ip_addr(self.addr, self.prefix)
-    }
-    fn end_addr(self: &Self) -> Ipv4Addr {
-        let start = mask_ip_addr(self.addr, self.prefix);
-        &start | Ipv4Addr::from(!make_mask(self.prefix))
-    }
-}
-
-impl std::convert::TryFrom<(Ipv4Addr, u8)> for Ipv4Subnet {
-    type Error = &'static str;
-    fn try_from(t: (Ipv4Addr, u8)) -> Result<Self, <Self as TryFrom<(Ipv4Addr, u8)>>::Error> {
-        if t.1 > 32 {
-            Err("Invalid prefix")
-        } else {
-            Ok(Self {
-                addr: t.0,
-                prefix: t.1,
-            })
-        }
-    }
-}
-
-impl std::convert::From<&Ipv4Range> for Ipv4Subnet {
-    fn from(iprange: &Ipv4Range) -> Self {
-        Self {
-            addr: iprange.start,
-            prefix: iprange.get_prefix(),
-        }
-    }
-}
-
-impl std::fmt::Display for Ipv4Subnet {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}/{}", self.addr, self.prefix)
-    }
-}
-
-/**
- * Expects a sorted ranges, in non-decreasing order.
- */
-fn merge_ranges(ranges: &mut Vec<Ipv4Range>) {
-    let n = ranges.len();
-    if n < 2 {
-        return;
-    }
-
-    let mut j = 0;
-    for i in 1..n {
-        // It merges not only overlapping subnets, but also
-        // adjacent subnets.
-        // eg: 192.168.24.2.2/32, 192.168.24.2.3/32 => 192.168.24.2.2/31
-        let start: u32 = ranges[i].start.into();
-        let end: u32 = ranges[j].end.into();
-        if start > end + 1 {
-            j += 1;
-            ranges[j] = ranges[i];
-        } else {
-            ranges[j].end = std::cmp::max(ranges[j].end, ranges[i].end);
-        }
-    }
-    j += 1;
-    ranges.drain(j..);
-}
-
 fn get_output_type(input_type: InputType, output_type: Option<OutputType>) -> OutputType {
     match output_type {
         Some(contype) => contype,
@@ -229,29 +117,22 @@ fn get_output_type(input_type: InputType, output_type: Option<OutputType>) -> Ou
     }
 }
 
-fn make_mask(prefix: u8) -> u32 {
-    if prefix == 0 {
-        return 0;
-    }
-    let mask: u32 = 0xffffffff;
-    if prefix < 32 {
-        let n = 32 - prefix;
-        return (mask >> n) << n;
-    }
-    return mask;
-}
-
-fn mask_ip_addr(ip: Ipv4Addr, prefix: u8) -> Ipv4Addr {
-    return ip & Ipv4Addr::from(make_mask(prefix));
-}
-
-fn ipaddr_to_string(ipaddr: Ipv4Addr, output_type: OutputType, reverse_bytes: bool) -> String {
-    let ip: u32 = ipaddr.into();
-    let ip: u32 = if reverse_bytes { ip.swap_bytes() } else { ip };
+/// Renders a `width`-bit address (32 for IPv4, 128 for IPv6) held in `ip`
+/// according to `output_type`, applying byte-reversal at the address's own
+/// width rather than always widening to 128 bits.
+fn ip_to_string(ip: u128, width: u8, output_type: OutputType, reverse_bytes: bool) -> String {
+    let ip = if !reverse_bytes {
+        ip
+    } else if width == 32 {
+        (ip as u32).swap_bytes() as u128
+    } else {
+        ip.swap_bytes()
+    };
     match output_type {
         OutputType::DecaDecimal => format!("{}", ip),
         OutputType::HexaDecimal => format!("{:#x}", ip),
-        OutputType::IpQuad => format!("{}", Ipv4Addr::from(ip)),
+        OutputType::IpQuad if width == 32 => format!("{}", Ipv4Addr::from(ip as u32)),
+        OutputType::IpQuad => format!("{}", Ipv6Addr::from(ip)),
     }
 }
 
@@ -277,7 +158,7 @@ fn main() {
     } else {
         // Enter filter mode.
         // Read from stdin and print to stdout
-        process_stdin(Config::default_config());
+        process_stdin(&Config::default_config());
     }
 }
 
@@ -285,8 +166,13 @@ fn process_args(itr: &mut std::env::Args) -> () {
     let mut config = Config::default_config();
     itr.next(); // Skip program name.
     let mut no_args = true;
-    let mut vec = Vec::<Ipv4Range>::new();
+    let mut vec = Vec::<IpRange>::new();
     let mut range_merge = false;
+    let mut contains_mode = false;
+    let mut container: Option<IpRange> = None;
+    let mut exclude_mode = false;
+    let mut exclude_base: Option<IpRange> = None;
+    let mut exclude_holes: Vec<IpRange> = Vec::new();
     for a in itr {
         if a == "--reverse-bytes" || a == "-r" {
             config.reverse_bytes = true;
@@ -298,14 +184,43 @@ fn process_args(itr: &mut std::env::Args) -> () {
             config.output_type = Some(OutputType::IpQuad);
         } else if a == "--merge-ranges" || a == "-m" {
             range_merge = true;
+        } else if a == "--contains" || a == "-c" {
+            contains_mode = true;
+        } else if a == "--count" || a == "-n" {
+            config.count_mode = true;
+        } else if a == "--enumerate" {
+            config.enumerate_mode = true;
+        } else if a == "--exclude" {
+            exclude_mode = true;
+        } else if a == "--json" {
+            config.json = true;
         } else {
             no_args = false;
+            if contains_mode {
+                if let Some(ref c) = container {
+                    process_contains(c, &a);
+                } else {
+                    match IpRange::parse(&a) {
+                        Ok(r) => container = Some(r),
+                        Err(e) => println!("{}: {}", e, a),
+                    }
+                }
+                continue;
+            }
+            if exclude_mode {
+                match IpRange::parse(&a) {
+                    Ok(r) if exclude_base.is_none() => exclude_base = Some(r),
+                    Ok(r) => exclude_holes.push(r),
+                    Err(e) => println!("{}: {}", e, a),
+                }
+                continue;
+            }
             if range_merge {
-                if let Ok(range) = Ipv4Range::parse_range(&a) {
+                if let Ok(range) = IpRange::parse_range(&a) {
                     vec.push(range);
                     continue;
                 }
-                process_ranges(&mut vec);
+                process_ranges(&mut vec, &config);
                 vec.clear();
             }
             process_ipaddress(&a, &config);
@@ -315,16 +230,129 @@ fn process_args(itr: &mut std::env::Args) -> () {
     // Enter filter mode.
     // Read from stdin and print to stdout
     if no_args {
-        process_stdin(config);
+        process_stdin(&config);
     }
 
     if range_merge {
-        process_ranges(&mut vec);
+        process_ranges(&mut vec, &config);
         vec.clear();
     }
+
+    if exclude_mode {
+        process_exclude(exclude_base, &exclude_holes, &config);
+    }
+}
+
+fn process_exclude(base: Option<IpRange>, holes: &[IpRange], config: &Config) {
+    let Some(base) = base else {
+        println!("--exclude requires a base address, subnet or range");
+        return;
+    };
+    match base {
+        IpRange::V4(base) => {
+            let holes: Vec<Ipv4Range> = holes
+                .iter()
+                .filter_map(|h| match h {
+                    IpRange::V4(r) => Some(*r),
+                    IpRange::V6(_) => {
+                        println!("{}: {}", "Address family mismatch", h);
+                        None
+                    }
+                })
+                .collect();
+            let remaining = base.exclude(&holes);
+            print_range_vec(&remaining, config.json);
+            let subnets: Vec<Ipv4Subnet> = remaining.iter().flat_map(|r| r.to_subnets()).collect();
+            print_subnet_vec(&subnets, config.json);
+        }
+        IpRange::V6(base) => {
+            let holes: Vec<Ipv6Range> = holes
+                .iter()
+                .filter_map(|h| match h {
+                    IpRange::V6(r) => Some(*r),
+                    IpRange::V4(_) => {
+                        println!("{}: {}", "Address family mismatch", h);
+                        None
+                    }
+                })
+                .collect();
+            let remaining = base.exclude(&holes);
+            print_range_vec(&remaining, config.json);
+            let subnets: Vec<Ipv6Subnet> = remaining.iter().flat_map(|r| r.to_subnets()).collect();
+            print_subnet_vec(&subnets, config.json);
+        }
+    }
 }
 
-fn print_range_vec(vec: &Vec<Ipv4Range>) {
+fn process_contains(container: &IpRange, candidate: &str) {
+    match IpRange::parse(candidate) {
+        Ok(range) => {
+            let verdict = if container.contains(&range) {
+                "is in"
+            } else {
+                "is not in"
+            };
+            println!("{} {} {}", candidate, verdict, container);
+        }
+        Err(e) => println!("{}: {}", e, candidate),
+    }
+}
+
+/// Implemented by the range/subnet types so `print_range_vec`/
+/// `print_subnet_vec` can render each element as a JSON object (rather than
+/// quoting its `Display` string) for `--json` mode.
+trait JsonItem {
+    fn json(&self) -> String;
+}
+
+impl JsonItem for Ipv4Range {
+    fn json(&self) -> String {
+        format!("{{\"start\": \"{}\", \"end\": \"{}\"}}", self.start(), self.end())
+    }
+}
+
+impl JsonItem for Ipv6Range {
+    fn json(&self) -> String {
+        format!("{{\"start\": \"{}\", \"end\": \"{}\"}}", self.start(), self.end())
+    }
+}
+
+impl JsonItem for Ipv4Subnet {
+    fn json(&self) -> String {
+        format!(
+            "{{\"network\": \"{}\", \"prefix\": {}, \"host_count\": {}}}",
+            self.network(),
+            self.prefix(),
+            Ipv4Range::from(self).host_count()
+        )
+    }
+}
+
+impl JsonItem for Ipv6Subnet {
+    fn json(&self) -> String {
+        format!(
+            "{{\"network\": \"{}\", \"prefix\": {}, \"host_count\": {}}}",
+            self.network(),
+            self.prefix(),
+            Ipv6Range::from(self).host_count()
+        )
+    }
+}
+
+fn json_array<T: JsonItem>(vec: &Vec<T>) -> String {
+    let items: Vec<String> = vec.iter().map(|v| v.json()).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn print_range_vec<T: std::fmt::Display + JsonItem>(vec: &Vec<T>, json: bool) {
+    if json {
+        println!("{}", json_array(vec));
+        return;
+    }
+    if vec.is_empty() {
+        println!("[]");
+        return;
+    }
     print!("[{}", vec[0]);
     for i in 1..vec.len() {
         print!(", {}", vec[i]);
@@ -332,7 +360,11 @@ fn print_range_vec(vec: &Vec<Ipv4Range>) {
     println!("]");
 }
 
-fn print_subnet_vec(vec: &Vec<Ipv4Subnet>) {
+fn print_subnet_vec<T: std::fmt::Display + JsonItem>(vec: &Vec<T>, json: bool) {
+    if json {
+        println!("{}", json_array(vec));
+        return;
+    }
     if vec.is_empty() {
         println!("[]");
         return;
@@ -344,26 +376,53 @@ fn print_subnet_vec(vec: &Vec<Ipv4Subnet>) {
     println!("]");
 }
 
-fn process_ranges(vec: &mut Vec<Ipv4Range>) -> () {
+fn process_ranges(vec: &mut Vec<IpRange>, config: &Config) -> () {
     if vec.is_empty() {
         return;
     }
-    vec.sort();
-    merge_ranges(vec);
-    print_range_vec(&vec);
-
-    let mut vec2: Vec<Ipv4Subnet> = Vec::new();
-    for i in 0..vec.len() {
-        let tmp = ip_range_to_subnets(vec[i]);
-        vec2.extend(tmp.iter());
+
+    let mut v4: Vec<Ipv4Range> = Vec::new();
+    let mut v6: Vec<Ipv6Range> = Vec::new();
+    for range in vec.drain(..) {
+        match range {
+            IpRange::V4(r) => v4.push(r),
+            IpRange::V6(r) => v6.push(r),
+        }
+    }
+
+    if !v4.is_empty() {
+        v4.sort();
+        Ipv4Range::merge_ranges(&mut v4);
+        print_range_vec(&v4, config.json);
+
+        let mut subnets: Vec<Ipv4Subnet> = Vec::new();
+        for r in &v4 {
+            subnets.extend(r.to_subnets());
+        }
+        print_subnet_vec(&subnets, config.json);
+    }
+
+    if !v6.is_empty() {
+        v6.sort();
+        Ipv6Range::merge_ranges(&mut v6);
+        print_range_vec(&v6, config.json);
+
+        let mut subnets: Vec<Ipv6Subnet> = Vec::new();
+        for r in &v6 {
+            subnets.extend(r.to_subnets());
+        }
+        print_subnet_vec(&subnets, config.json);
     }
-    print_subnet_vec(&vec2);
 }
 
-fn process_stdin(config: Config) -> () {
+fn process_stdin(config: &Config) -> () {
     let config = Config {
         filter_mode: true,
-        ..config
+        reverse_bytes: config.reverse_bytes,
+        output_type: config.output_type,
+        count_mode: config.count_mode,
+        enumerate_mode: config.enumerate_mode,
+        json: config.json,
     };
     let input = stdin();
     for line in input.lock().lines() {
@@ -379,102 +438,92 @@ fn process_stdin(config: Config) -> () {
     }
 }
 
-fn count_suffix_zero_bits(ip: u64) -> u8 {
-    let mut i = 0;
-    let mut ip = ip;
-    while (i <= 32) && ((ip & 0x1) == 0x0) {
-        i += 1;
-        ip >>= 1
-    }
-    return i;
-}
-
-fn ip_range_to_subnets(range: Ipv4Range) -> Vec<Ipv4Subnet> {
-    let mut vec: Vec<Ipv4Subnet> = Vec::new();
-    let start: u32 = range.start.into();
-    let end: u32 = range.end.into();
-    let mut start: u64 = start as u64;
-    let end: u64 = end as u64;
-    while start <= end {
-        let mut s: u8 = count_suffix_zero_bits(start);
-        let mut diff: u64 = (1u64 << s) - 1;
-        while (start + diff) > end {
-            diff >>= 1;
-            s -= 1;
-        }
-        vec.push(Ipv4Subnet {
-            addr: Ipv4Addr::from(start as u32),
-            prefix: 32 - s,
-        });
-        start += diff + 1;
-    }
-    return vec;
-}
-
 fn process_ipaddress(a: &str, config: &Config) {
-    if let Some(n) = a.find('/') {
-        // A subnet (eg. 192.168.18.0/24)
-        if let Ok(prefix) = u8::from_str(&a[n + 1..]) {
-            if let Ok(addr) = Ipv4Addr::from_str(&a[..n]) {
-                if let Ok(subnet) = Ipv4Subnet::try_from((addr, prefix)) {
-                    let output = format!("{subnet}")
-                        + "\n"
-                        + &format!("{subnet}")
-                        + " = "
-                        + &format!("{}", Ipv4Range::from(&subnet));
-                    print_output(&output, &a, &config);
-                    return;
-                }
-            }
+    if config.count_mode || config.enumerate_mode {
+        match IpRange::parse(a) {
+            Ok(range) if config.enumerate_mode => process_enumerate(&range),
+            Ok(range) => println!("{}", process_count(&range)),
+            Err(e) => println!("{}: {}", e, a),
         }
-        println!("Invalid IP subnet: {}", a);
-    } else if let Some(n) = a.find('-') {
-        // A range (eg. 192.168.18.0-192.168.18.255)
-        if let Ok(iprange_start) = Ipv4Addr::from_str(a[..n].trim()) {
-            if let Ok(iprange_end) = Ipv4Addr::from_str(a[n + 1..].trim()) {
-                if let Ok(iprange) = Ipv4Range::try_from((iprange_start, iprange_end)) {
-                    let subnet = Ipv4Subnet::from(&iprange);
+        return;
+    }
+    if a.find('/').is_some() || a.find('-').is_some() {
+        // A subnet (eg. 192.168.18.0/24) or a range (eg. 192.168.18.0-192.168.18.255),
+        // of either address family.
+        match IpRange::parse_range(a) {
+            Ok(range) => {
+                let subnet = IpSubnet::from(&range);
+                if config.json {
+                    println!("{}", json_subnet_object(&subnet, &range));
+                } else {
                     let output = format!("{subnet}")
                         + "\n"
                         + &format!("{subnet}")
                         + " = "
-                        + &format!("{}", Ipv4Range::from(&subnet));
-                    print_output(&output, &a, &config);
-                    return;
+                        + &format!("{range}");
+                    print_output(&output, a, config);
                 }
             }
+            Err(e) => println!("{}: {}", e, a),
         }
-        println!("Invalid IP range: {}", a);
-    } else if let Ok(addr) = Ipv4Addr::from_str(&a) {
+    } else if let Ok(addr) = Ipv4Addr::from_str(a) {
         // Dotted quad IPv4 address (eg. 192.168.18.0)
-        let input_type = InputType::IpQuad;
-        let output_type = get_output_type(input_type, config.output_type);
-        let output = ipaddr_to_string(addr, output_type, config.reverse_bytes);
-        print_output(&output, &a, &config);
+        if config.json {
+            println!("{}", json_address_object(u32::from(addr) as u128, 32));
+            return;
+        }
+        let output_type = get_output_type(InputType::IpQuad, config.output_type);
+        let output = ip_to_string(u32::from(addr) as u128, 32, output_type, config.reverse_bytes);
+        print_output(&output, a, config);
     } else if let Ok(ip) = a.parse::<u32>() {
-        // A de number that can treated as an IPv4 address
-        // A decimal number as IPv4 address
-        let addr = Ipv4Addr::from(ip);
-        let input_type = InputType::DecaDecimal;
-        let output_type = get_output_type(input_type, config.output_type);
-        let output = ipaddr_to_string(addr, output_type, config.reverse_bytes);
-        print_output(&output, &a, &config);
+        // A decimal number that can be treated as an IPv4 address
+        if config.json {
+            println!("{}", json_address_object(ip as u128, 32));
+            return;
+        }
+        let output_type = get_output_type(InputType::DecaDecimal, config.output_type);
+        let output = ip_to_string(ip as u128, 32, output_type, config.reverse_bytes);
+        print_output(&output, a, config);
+    } else if let Ok(addr) = Ipv6Addr::from_str(a) {
+        // An IPv6 address (eg. 2001:db8::1)
+        if config.json {
+            println!("{}", json_address_object(u128::from(addr), 128));
+            return;
+        }
+        let output_type = get_output_type(InputType::IpQuad, config.output_type);
+        let output = ip_to_string(u128::from(addr), 128, output_type, config.reverse_bytes);
+        print_output(&output, a, config);
+    } else if let Ok(ip) = a.parse::<u128>() {
+        // A decimal number too large for an IPv4 address, treated as IPv6
+        if config.json {
+            println!("{}", json_address_object(ip, 128));
+            return;
+        }
+        let output_type = get_output_type(InputType::DecaDecimal, config.output_type);
+        let output = ip_to_string(ip, 128, output_type, config.reverse_bytes);
+        print_output(&output, a, config);
     } else {
-        // See if it's a hexadecimal number as IPv4 address
-        let ip;
-        if let Some(a2) = a.strip_prefix("0x") {
-            // hexadecimal number with "0x" prefix?
-            ip = u32::from_str_radix(&a2, 16);
-        } else {
-            // hexadecimal number without a "0x" prefix?
-            ip = u32::from_str_radix(&a, 16);
+        // See if it's a hexadecimal number, as a 32-bit (IPv4) or
+        // 128-bit (IPv6) address.
+        let hex = a.strip_prefix("0x").unwrap_or(a);
+        if let Ok(ip) = u32::from_str_radix(hex, 16) {
+            if config.json {
+                println!("{}", json_address_object(ip as u128, 32));
+                return;
+            }
+            let output_type = get_output_type(InputType::HexaDecimal, config.output_type);
+            let output = ip_to_string(ip as u128, 32, output_type, config.reverse_bytes);
+            print_output(&output, a, config);
+            return;
         }
-        if let Ok(ip) = ip {
-            let addr = Ipv4Addr::from(ip);
-            let input_type = InputType::HexaDecimal;
-            let output_type = get_output_type(input_type, config.output_type);
-            let output = ipaddr_to_string(addr, output_type, config.reverse_bytes);
-            print_output(&output, &a, &config);
+        if let Ok(ip) = u128::from_str_radix(hex, 16) {
+            if config.json {
+                println!("{}", json_address_object(ip, 128));
+                return;
+            }
+            let output_type = get_output_type(InputType::HexaDecimal, config.output_type);
+            let output = ip_to_string(ip, 128, output_type, config.reverse_bytes);
+            print_output(&output, a, config);
             return;
         }
         // Not even a hexadecimal number
@@ -482,6 +531,73 @@ fn process_ipaddress(a: &str, config: &Config) {
     }
 }
 
+/// Renders a single address as a JSON object with its decimal, hex, quad
+/// and byte-reversed forms, for `--json` mode.
+fn json_address_object(ip: u128, width: u8) -> String {
+    let (hex, quad, reversed) = if width == 32 {
+        let ip = ip as u32;
+        (
+            format!("{:#x}", ip),
+            format!("{}", Ipv4Addr::from(ip)),
+            format!("{}", Ipv4Addr::from(ip.swap_bytes())),
+        )
+    } else {
+        (
+            format!("{:#x}", ip),
+            format!("{}", Ipv6Addr::from(ip)),
+            format!("{}", Ipv6Addr::from(ip.swap_bytes())),
+        )
+    };
+    format!(
+        "{{\"decimal\": {}, \"hex\": \"{}\", \"quad\": \"{}\", \"reverse_bytes\": \"{}\"}}",
+        ip, hex, quad, reversed
+    )
+}
+
+/// Renders a subnet/range lookup as a JSON object, for `--json` mode.
+fn json_subnet_object(subnet: &IpSubnet, range: &IpRange) -> String {
+    let (network, broadcast, prefix, host_count) = match subnet {
+        IpSubnet::V4(s) => (
+            format!("{}", s.network()),
+            format!("{}", s.broadcast()),
+            s.prefix(),
+            Ipv4Range::from(s).host_count() as u128,
+        ),
+        IpSubnet::V6(s) => (
+            format!("{}", s.network()),
+            format!("{}", s.broadcast()),
+            s.prefix(),
+            Ipv6Range::from(s).host_count(),
+        ),
+    };
+    format!(
+        "{{\"network\": \"{}\", \"broadcast\": \"{}\", \"prefix\": {}, \"host_count\": {}, \"range\": \"{}\"}}",
+        network, broadcast, prefix, host_count, range
+    )
+}
+
+fn process_count(range: &IpRange) -> String {
+    match range {
+        IpRange::V4(r) => format!("{}", r.host_count()),
+        IpRange::V6(r) => format!("{}", r.host_count()),
+    }
+}
+
+fn process_enumerate(range: &IpRange) {
+    match range {
+        IpRange::V4(r) => {
+            for addr in r.iter() {
+                println!("{}", addr);
+            }
+        }
+        IpRange::V6(r) => {
+            for addr in r.iter() {
+                println!("{}", addr);
+            }
+        }
+    }
+}
+
 fn print_output(output: &str, input: &str, config: &Config) -> () {
     if config.filter_mode {
         println!("{}", output);